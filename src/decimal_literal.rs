@@ -0,0 +1,57 @@
+// Splits a decimal/scientific-notation literal like "-1.25e-3" into the
+// pieces `from_decimal` needs: a sign, the significant digits with the
+// decimal point removed, and the power of ten that point shift plus any `e`
+// suffix amounts to. "-1.25e-3" becomes (true, 125, -3 - 2) = (true, 125, -5),
+// i.e. -125 * 10**-5 == -0.00125.
+
+pub struct DecimalLiteral {
+    pub is_negative: bool,
+    /// `None` when the digits don't fit in a `u64` (too many significant digits).
+    pub digits: Option<u64>,
+    pub exponent: i32,
+}
+
+pub fn parse(literal: &str) -> DecimalLiteral {
+    let (is_negative, literal) = match literal.strip_prefix('-') {
+        Some(rest) => (true, rest),
+        None => (false, literal.strip_prefix('+').unwrap_or(literal)),
+    };
+
+    let (mantissa, exponent_suffix) = match literal.split_once(['e', 'E']) {
+        Some((mantissa, exponent_suffix)) => (mantissa, exponent_suffix.parse::<i32>().unwrap_or(0)),
+        None => (literal, 0),
+    };
+
+    let (integer_part, fractional_part) = match mantissa.split_once('.') {
+        Some((integer_part, fractional_part)) => (integer_part, fractional_part),
+        None => (mantissa, ""),
+    };
+
+    let digit_str = format!("{}{}", integer_part, fractional_part);
+    let digits = digit_str.parse::<u64>().ok();
+    let exponent = exponent_suffix - fractional_part.len() as i32;
+
+    DecimalLiteral { is_negative, digits, exponent }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse() {
+        let literal = parse("-1.25e-3");
+        assert!(literal.is_negative);
+        assert_eq!(literal.digits, Some(125));
+        assert_eq!(literal.exponent, -5);
+
+        let literal = parse("123.456");
+        assert!(!literal.is_negative);
+        assert_eq!(literal.digits, Some(123456));
+        assert_eq!(literal.exponent, -3);
+
+        let literal = parse("1e20");
+        assert_eq!(literal.digits, Some(1));
+        assert_eq!(literal.exponent, 20);
+    }
+}
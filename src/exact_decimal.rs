@@ -0,0 +1,93 @@
+// Every finite IEEE 754 float equals `sign * significand * 2**exponent` for
+// an integer significand and a signed exponent, so it has an exact (if
+// sometimes very long) decimal expansion. `2**-e == 5**-e / 10**-e`, so for a
+// negative exponent the expansion is `significand * 5**(-e)` with the decimal
+// point shifted `-e` places to the left; for a non-negative exponent it is
+// just `significand << e`. Both cases reduce to repeatedly multiplying a
+// base-10 digit string by a single small digit (2 or 5), a schoolbook
+// long multiplication that stays exact no matter how many digits it grows to.
+
+/// Big-endian (most significant digit first) base-10 digits, no leading zero
+/// unless the value itself is zero.
+fn digits_of(mut n: u64) -> Vec<u8> {
+    if n == 0 {
+        return vec![0];
+    }
+    let mut digits = Vec::new();
+    while n > 0 {
+        digits.push((n % 10) as u8);
+        n /= 10;
+    }
+    digits.reverse();
+    digits
+}
+
+/// Multiplies a big-endian digit string by a single small digit, carrying as
+/// it goes, the way you would by hand.
+fn multiply_by_digit(digits: &[u8], multiplier: u8) -> Vec<u8> {
+    let mut out = Vec::with_capacity(digits.len() + 1);
+    let mut carry: u32 = 0;
+    for &digit in digits.iter().rev() {
+        let product = digit as u32 * multiplier as u32 + carry;
+        out.push((product % 10) as u8);
+        carry = product / 10;
+    }
+    while carry > 0 {
+        out.push((carry % 10) as u8);
+        carry /= 10;
+    }
+    out.reverse();
+    while out.len() > 1 && out[0] == 0 {
+        out.remove(0);
+    }
+    out
+}
+
+fn multiply_by_digit_repeated(mut digits: Vec<u8>, multiplier: u8, times: u32) -> Vec<u8> {
+    for _ in 0..times {
+        digits = multiply_by_digit(&digits, multiplier);
+    }
+    digits
+}
+
+/// Renders `sign * significand * 2**exponent` as an exact decimal string.
+pub fn exact_decimal_string(is_negative: bool, significand: u64, exponent: i32) -> String {
+    let digits = if exponent >= 0 {
+        multiply_by_digit_repeated(digits_of(significand), 2, exponent as u32)
+    } else {
+        let frac_len = (-exponent) as usize;
+        let mut digits = multiply_by_digit_repeated(digits_of(significand), 5, frac_len as u32);
+        if digits.len() <= frac_len {
+            let mut padded = vec![0u8; frac_len - digits.len() + 1];
+            padded.extend_from_slice(&digits);
+            digits = padded;
+        }
+        let split = digits.len() - frac_len;
+        let digit_chars: String = digits.iter().map(|d| (b'0' + d) as char).collect();
+        let (integer_part, fractional_part) = digit_chars.split_at(split);
+        return format!("{}{}.{}", if is_negative { "-" } else { "" }, integer_part, fractional_part);
+    };
+
+    let digit_chars: String = digits.iter().map(|d| (b'0' + d) as char).collect();
+    format!("{}{}", if is_negative { "-" } else { "" }, digit_chars)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_exact_decimal_string_integer() {
+        // 1.0 == 1 * 2**0
+        assert_eq!(exact_decimal_string(false, 1, 0), "1");
+        // 8.0 == 1 * 2**3
+        assert_eq!(exact_decimal_string(false, 1, 3), "8");
+        assert_eq!(exact_decimal_string(true, 1, 3), "-8");
+    }
+
+    #[test]
+    fn test_exact_decimal_string_fraction() {
+        // 0.1_f32's nearest significand/exponent pair, which is NOT exactly 0.1.
+        assert_eq!(exact_decimal_string(false, 13421773, -27), "0.100000001490116119384765625");
+    }
+}
@@ -0,0 +1,192 @@
+use std::fmt;
+
+// Generalizes the parser/decoder over IEEE 754 binary float widths. Every
+// binary format (binary16, binary32, binary64, ...) lays a value out as
+// sign | exponent | mantissa and reconstructs a normal as
+// `significand * 2**k` with an implicit hidden bit, differing only in how
+// wide each field is and what the exponent bias is. Implementing this trait
+// for a new width is enough to make it work with the whole pipeline.
+pub trait IeeeFloat:
+    Copy
+    + PartialEq
+    + fmt::Display
+    + std::ops::Add<Output = Self>
+    + std::ops::Mul<Output = Self>
+    + std::ops::Div<Output = Self>
+{
+    /// Width of the exponent field, in bits.
+    const EXPONENT_BITS: u32;
+    /// Width of the mantissa field, in bits.
+    const MANTISSA_BITS: u32;
+    /// Value subtracted from the raw exponent field to get the true exponent
+    /// of a normal number.
+    const BIAS: i32;
+    /// Name printed in output headers (e.g. "f32").
+    const TYPE_NAME: &'static str;
+
+    /// Largest integer significand still exactly representable, i.e. `2**MANTISSA_DIGITS`.
+    const MAX_EXACT_SIGNIFICAND: u64;
+    /// Largest `k` for which `10**k` is itself exactly representable.
+    const MAX_EXACT_POW10: u32;
+
+    /// Reference constants the standard library exposes for this type,
+    /// paired with their printed values, for the reference-card output.
+    fn reference_constants() -> Vec<(&'static str, String)>;
+
+    fn to_bits(self) -> u64;
+    fn is_nan_value(self) -> bool;
+    fn is_infinite_value(self) -> bool;
+
+    fn zero() -> Self;
+    fn one() -> Self;
+    fn infinity() -> Self;
+    fn nan() -> Self;
+
+    /// `(-1)**exp`, i.e. 1.0 for an even exponent, -1.0 for an odd one.
+    fn neg_one_pow(exp: u32) -> Self;
+    /// `2**exp` for the signed true exponent.
+    fn radix_pow(exp: i32) -> Self;
+    /// The weight contributed by mantissa bit `bit_index`, i.e. `2**(bit_index - MANTISSA_BITS)`.
+    fn mantissa_bit_weight(bit_index: u32) -> Self;
+
+    /// Converts an integer significand known to fit within `MAX_EXACT_SIGNIFICAND` to `Self` exactly.
+    fn from_exact_u64(n: u64) -> Self;
+    /// `10**exp` for `exp <= MAX_EXACT_POW10`, exact.
+    fn exact_pow10(exp: u32) -> Self;
+}
+
+impl IeeeFloat for f32 {
+    const EXPONENT_BITS: u32 = 8;
+    const MANTISSA_BITS: u32 = 23;
+    const BIAS: i32 = 127;
+    const TYPE_NAME: &'static str = "f32";
+    const MAX_EXACT_SIGNIFICAND: u64 = 1 << 24;
+    const MAX_EXACT_POW10: u32 = 10;
+
+    fn reference_constants() -> Vec<(&'static str, String)> {
+        vec![
+            ("MANTISSA_DIGITS", f32::MANTISSA_DIGITS.to_string()),
+            ("DIGITS", f32::DIGITS.to_string()),
+            ("EPSILON", f32::EPSILON.to_string()),
+            ("MIN_POSITIVE", f32::MIN_POSITIVE.to_string()),
+            ("MIN", f32::MIN.to_string()),
+            ("MAX", f32::MAX.to_string()),
+        ]
+    }
+
+    fn to_bits(self) -> u64 {
+        f32::to_bits(self) as u64
+    }
+
+    fn is_nan_value(self) -> bool {
+        self.is_nan()
+    }
+
+    fn is_infinite_value(self) -> bool {
+        self.is_infinite()
+    }
+
+    fn zero() -> Self {
+        0.0
+    }
+
+    fn one() -> Self {
+        1.0
+    }
+
+    fn infinity() -> Self {
+        f32::INFINITY
+    }
+
+    fn nan() -> Self {
+        f32::NAN
+    }
+
+    fn neg_one_pow(exp: u32) -> Self {
+        (-1.0_f32).powi(exp as i32)
+    }
+
+    fn radix_pow(exp: i32) -> Self {
+        2.0_f32.powi(exp)
+    }
+
+    fn mantissa_bit_weight(bit_index: u32) -> Self {
+        2.0_f32.powi(bit_index as i32 - Self::MANTISSA_BITS as i32)
+    }
+
+    fn from_exact_u64(n: u64) -> Self {
+        n as f32
+    }
+
+    fn exact_pow10(exp: u32) -> Self {
+        10.0_f32.powi(exp as i32)
+    }
+}
+
+impl IeeeFloat for f64 {
+    const EXPONENT_BITS: u32 = 11;
+    const MANTISSA_BITS: u32 = 52;
+    const BIAS: i32 = 1023;
+    const TYPE_NAME: &'static str = "f64";
+    const MAX_EXACT_SIGNIFICAND: u64 = 1 << 53;
+    const MAX_EXACT_POW10: u32 = 22;
+
+    fn reference_constants() -> Vec<(&'static str, String)> {
+        vec![
+            ("MANTISSA_DIGITS", f64::MANTISSA_DIGITS.to_string()),
+            ("DIGITS", f64::DIGITS.to_string()),
+            ("EPSILON", f64::EPSILON.to_string()),
+            ("MIN_POSITIVE", f64::MIN_POSITIVE.to_string()),
+            ("MIN", f64::MIN.to_string()),
+            ("MAX", f64::MAX.to_string()),
+        ]
+    }
+
+    fn to_bits(self) -> u64 {
+        f64::to_bits(self)
+    }
+
+    fn is_nan_value(self) -> bool {
+        self.is_nan()
+    }
+
+    fn is_infinite_value(self) -> bool {
+        self.is_infinite()
+    }
+
+    fn zero() -> Self {
+        0.0
+    }
+
+    fn one() -> Self {
+        1.0
+    }
+
+    fn infinity() -> Self {
+        f64::INFINITY
+    }
+
+    fn nan() -> Self {
+        f64::NAN
+    }
+
+    fn neg_one_pow(exp: u32) -> Self {
+        (-1.0_f64).powi(exp as i32)
+    }
+
+    fn radix_pow(exp: i32) -> Self {
+        2.0_f64.powi(exp)
+    }
+
+    fn mantissa_bit_weight(bit_index: u32) -> Self {
+        2.0_f64.powi(bit_index as i32 - Self::MANTISSA_BITS as i32)
+    }
+
+    fn from_exact_u64(n: u64) -> Self {
+        n as f64
+    }
+
+    fn exact_pow10(exp: u32) -> Self {
+        10.0_f64.powi(exp as i32)
+    }
+}
@@ -1,4 +1,12 @@
+mod decimal_literal;
+mod exact_decimal;
+mod ieee_float;
+
 use std::env;
+use std::fmt;
+use std::str::FromStr;
+
+use ieee_float::IeeeFloat;
 
 // In computer science, we leverage Scientific Notation to represent
 // floating-point numbers, because it doesn't only have a fixed width, but also
@@ -16,84 +24,435 @@ use std::env;
 //  |             |                                     |
 // Sign bit     Exponent                            Mantissa
 // The radix is a constant value, 2, for binary numbers. Also the standard introduces another
-// constant value for calculating the actual exponent, which is 127.
+// constant value for calculating the actual exponent, the bias, which is 127 for binary32 and
+// 1023 for binary64 (see `ieee_float::IeeeFloat`).
 // Thus a floating-point number can be calculated by this equation:
 //          n = -1**sign_bit * mantissa * Radix**(exponent-Bias)
 //
 // Note:
 // * The floating-point numbers 0 and -0 are equal but have different bit patterns.
 // * The NAN floating-point values have identical bit patterns but are not equal.
-
-const BIAS: i32 = 127;
-const RADIX: f32 = 2.0;
+//
+// The equation above only holds for "normal" numbers, where the exponent field
+// is neither all zeros nor all ones. The remaining bit patterns are special:
+// * exponent field all zeros: the implicit leading mantissa bit is 0 instead of
+//   1, and the effective exponent is pinned at 1-Bias instead of 0-Bias. This
+//   covers both zero (mantissa field also zero) and the subnormals.
+// * exponent field all ones: a zero mantissa field means +-infinity, a nonzero
+//   one means NaN. Neither has a finite sign*mantissa*exponent reconstruction.
 
 fn main() {
-    let mut args = env::args();
-    args.next();
+    let args: Vec<String> = env::args().skip(1).collect();
+    let asn1_mode = args.iter().any(|arg| arg == "--asn1");
+    let type_name = args
+        .windows(2)
+        .find(|window| window[0] == "--type")
+        .map(|window| window[1].as_str())
+        .unwrap_or("f32");
+    let from_decimal = args.windows(2).find(|window| window[0] == "--from-decimal").map(|window| window[1].as_str());
+    let n = from_decimal.unwrap_or_else(|| extract_number_arg(&args));
+
+    match type_name {
+        "f32" => run::<f32>(n, asn1_mode, from_decimal.is_some()),
+        "f64" => run::<f64>(n, asn1_mode, from_decimal.is_some()),
+        other => panic!("Unsupported --type {}, expected f32 or f64", other),
+    }
+}
+
+// Picks the number out of the argument list, skipping `--asn1`, `--type <name>`,
+// and `--from-decimal <literal>`.
+fn extract_number_arg(args: &[String]) -> &str {
+    let mut i = 0;
+    while i < args.len() {
+        match args[i].as_str() {
+            "--asn1" => i += 1,
+            "--type" | "--from-decimal" => i += 2,
+            other => return other,
+        }
+    }
+    panic!("Please provide a floating-point number as a argument!");
+}
 
-    let n = args.next().expect("Please provide a floating-point number as a argument!");
-    let n: f32 = n.parse::<f32>().expect("Invalid floating-point number!");
+fn run<T>(n: &str, asn1_mode: bool, from_decimal: bool)
+where
+    T: IeeeFloat + FromStr,
+    T::Err: fmt::Debug,
+{
+    let n: T = if from_decimal {
+        let (value, fast_path_used) = from_decimal_literal::<T>(n);
+        let path = if fast_path_used { "fast path" } else { "fast path did not apply, fell back to str::parse" };
+        println!("\"{}\" parsed as {} ({})", n, value, path);
+        value
+    } else {
+        n.parse().expect("Invalid floating-point number!")
+    };
 
     let (sign_bits, exponent_bits, mantissa_bits) = parse(n);
-    let (sign_real_num, exponent_real_num, mantissa_real_num) = decode(sign_bits, exponent_bits, mantissa_bits);
+
+    if asn1_mode {
+        let encoded = encode_asn1_real::<T>(sign_bits, exponent_bits, mantissa_bits);
+        let hex = encoded.iter().map(|byte| format!("{:02x}", byte)).collect::<Vec<_>>().join(" ");
+        println!("{} as ASN.1 BER REAL -> {}", n, hex);
+        return;
+    }
+
+    let (sign_real_num, exponent_real_num, mantissa_real_num) = decode::<T>(sign_bits, exponent_bits, mantissa_bits);
     let n_recalculated = recalculate(sign_real_num, exponent_real_num, mantissa_real_num);
+    let category = classify::<T>(exponent_bits, mantissa_bits);
+
+    let exponent_width = T::EXPONENT_BITS as usize;
+    let mantissa_width = T::MANTISSA_BITS as usize;
+    let bit_col_width = mantissa_width; // the widest field; others pad out to it
+
+    let sign_str = format!("{:01b}", sign_bits);
+    let exponent_str = format!("{:0width$b}", exponent_bits, width = exponent_width);
+    let mantissa_str = format!("{:0width$b}", mantissa_bits, width = mantissa_width);
 
     println!("{} is recalculated by its parts (sign, exponent, mantissa) -> {}", n, n_recalculated);
-    println!("field     | as bits   | as real number");
-    println!("sign      | {:01b}    | {}", sign_bits, sign_real_num);
-    println!("exponent  | {:08b}    | {}", exponent_bits, exponent_real_num);
-    println!("mantissa  | {:023b}   | {}", mantissa_bits, mantissa_real_num);
+    println!("field     | {:<width$} | as real number", "as bits", width = bit_col_width);
+    println!("sign      | {:<width$} | {}", sign_str, sign_real_num, width = bit_col_width);
+    println!("exponent  | {:<width$} | {}", exponent_str, exponent_real_num, width = bit_col_width);
+    println!("mantissa  | {:<width$} | {}", mantissa_str, mantissa_real_num, width = bit_col_width);
+    println!("category  | {}", category);
+    println!("exact     | {}", exact_decimal_line::<T>(sign_bits, exponent_bits, mantissa_bits, &category));
+
+    println!();
+    println!("{} reference constants:", T::TYPE_NAME);
+    for (name, value) in T::reference_constants() {
+        println!("{:<15} | {}", name, value);
+    }
+}
+
+// Builds the nearest `T` to a decimal literal without delegating to
+// `str::parse`, so the tool can show *how* a decimal becomes bits. Uses the
+// fast-path idea from lexical-style parsers: if the integer significand fits
+// in the mantissa and the decimal exponent is within the table of exactly
+// representable powers of ten, `significand as T * 10**exp` (or `/ 10**-exp`)
+// rounds once and is provably correctly-rounded. Otherwise falls back to
+// `str::parse` and reports that the fast path didn't apply. Returns the
+// parsed value and whether the fast path was taken.
+fn from_decimal_literal<T>(literal: &str) -> (T, bool)
+where
+    T: IeeeFloat + FromStr,
+    T::Err: fmt::Debug,
+{
+    let parsed = decimal_literal::parse(literal);
+
+    if let Some(digits) = parsed.digits {
+        if digits <= T::MAX_EXACT_SIGNIFICAND && parsed.exponent.unsigned_abs() <= T::MAX_EXACT_POW10 {
+            let significand = T::from_exact_u64(digits);
+            let magnitude = if parsed.exponent >= 0 {
+                significand * T::exact_pow10(parsed.exponent as u32)
+            } else {
+                significand / T::exact_pow10((-parsed.exponent) as u32)
+            };
+            let value = T::neg_one_pow(parsed.is_negative as u32) * magnitude;
+            return (value, true);
+        }
+    }
+
+    let value: T = literal.parse().expect("Invalid floating-point number!");
+    (value, false)
 }
 
-fn parse(n: f32) -> (u32, u32, u32) {
+fn parse<T: IeeeFloat>(n: T) -> (u32, u32, u64) {
     let bits = n.to_bits();
 
-    let sign = (bits >> 31) & 1;
-    let exponent = (bits >> 23) & 0xff;
-    let mantissa = bits & 0x7fffff;
+    let sign = ((bits >> (T::EXPONENT_BITS + T::MANTISSA_BITS)) & 1) as u32;
+    let exponent_mask = (1u64 << T::EXPONENT_BITS) - 1;
+    let exponent = ((bits >> T::MANTISSA_BITS) & exponent_mask) as u32;
+    let mantissa_mask = (1u64 << T::MANTISSA_BITS) - 1;
+    let mantissa = bits & mantissa_mask;
 
     (sign, exponent, mantissa)
 }
 
-fn decode(sign_bits: u32, exponent_bits: u32, mantissa_bits: u32) -> (f32, f32, f32) {
-    let sign_real_num = (-1.0_f32).powf(sign_bits as f32);
+fn decode<T: IeeeFloat>(sign_bits: u32, exponent_bits: u32, mantissa_bits: u64) -> (T, T, T) {
+    let sign_real_num = T::neg_one_pow(sign_bits);
+    let exponent_all_ones = exponent_bits == (1u32 << T::EXPONENT_BITS) - 1;
+
+    // Exponent field all ones: +-infinity (zero mantissa) or NaN (nonzero
+    // mantissa). Neither reconstructs from a finite exponent/mantissa pair, so
+    // hand the symbolic value straight through recalculate().
+    if exponent_all_ones {
+        let exponent_real_num = T::infinity();
+        let mantissa_real_num = if mantissa_bits == 0 { T::zero() } else { T::nan() };
+        return (sign_real_num, exponent_real_num, mantissa_real_num);
+    }
+
+    // Exponent field all zeros: zero or subnormal. The implicit leading bit is
+    // 0 instead of 1, and the effective exponent is pinned at 1-Bias rather
+    // than 0-Bias.
+    let (implicit_bit, exponent_field) = if exponent_bits == 0 {
+        (T::zero(), 1 - T::BIAS)
+    } else {
+        (T::one(), exponent_bits as i32 - T::BIAS)
+    };
 
-    let exponent_real_num = (exponent_bits as i32) - BIAS;
-    let exponent_real_num = RADIX.powf(exponent_real_num as f32);
+    let exponent_real_num = T::radix_pow(exponent_field);
 
-    let mut mantissa_real_num: f32 = 1.0;
-    for i in 0..23 {
-        let mask = 1 << i;
-        let one_at_bit_i = mantissa_bits & mask;
-        if one_at_bit_i != 0 {
-            let i_ = i as f32;
-            let weight = 2_f32.powf(i_ - 23.0);
-            mantissa_real_num += weight;
+    let mut mantissa_real_num = implicit_bit;
+    for i in 0..T::MANTISSA_BITS {
+        let mask = 1u64 << i;
+        if mantissa_bits & mask != 0 {
+            mantissa_real_num = mantissa_real_num + T::mantissa_bit_weight(i);
         }
     }
 
     (sign_real_num, exponent_real_num, mantissa_real_num)
 }
 
-fn recalculate(sign_real_num: f32, exponent_real_num: f32, mantissa_real_num: f32) -> f32 {
+fn recalculate<T: IeeeFloat>(sign_real_num: T, exponent_real_num: T, mantissa_real_num: T) -> T {
+    if mantissa_real_num.is_nan_value() {
+        return T::nan();
+    }
+    if exponent_real_num.is_infinite_value() {
+        return sign_real_num * T::infinity();
+    }
+
     sign_real_num * exponent_real_num * mantissa_real_num
 }
 
+// Mirrors `std::num::FpCategory`, the classification the standard library
+// gives an `f32`/`f64`, computed purely from the already-parsed exponent and
+// mantissa fields rather than from the float value itself.
+#[derive(Debug, PartialEq, Eq)]
+enum FpCategory {
+    Zero,
+    Subnormal,
+    Normal,
+    Infinite,
+    Nan,
+}
+
+impl fmt::Display for FpCategory {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let name = match self {
+            FpCategory::Zero => "Zero",
+            FpCategory::Subnormal => "Subnormal",
+            FpCategory::Normal => "Normal",
+            FpCategory::Infinite => "Infinite",
+            FpCategory::Nan => "Nan",
+        };
+        write!(f, "{}", name)
+    }
+}
+
+fn classify<T: IeeeFloat>(exponent_bits: u32, mantissa_bits: u64) -> FpCategory {
+    let exponent_max = (1u32 << T::EXPONENT_BITS) - 1;
+    match (exponent_bits, mantissa_bits) {
+        (e, 0) if e == exponent_max => FpCategory::Infinite,
+        (e, _) if e == exponent_max => FpCategory::Nan,
+        (0, 0) => FpCategory::Zero,
+        (0, _) => FpCategory::Subnormal,
+        _ => FpCategory::Normal,
+    }
+}
+
+// Returns the unsigned integer significand and its base-2 exponent such that
+// |value| == significand * 2**exponent, i.e. the hidden bit folded back into
+// the mantissa. Only meaningful for Zero/Subnormal/Normal; infinities and NaN
+// have no such representation.
+fn significand_and_exponent<T: IeeeFloat>(exponent_bits: u32, mantissa_bits: u64) -> (u64, i32) {
+    if exponent_bits == 0 {
+        (mantissa_bits, 1 - T::BIAS - T::MANTISSA_BITS as i32)
+    } else {
+        (mantissa_bits | (1u64 << T::MANTISSA_BITS), exponent_bits as i32 - T::BIAS - T::MANTISSA_BITS as i32)
+    }
+}
+
+// Renders the exact decimal expansion of the parsed value, falling back to
+// the symbolic rendering for infinities and NaN, which have none.
+fn exact_decimal_line<T: IeeeFloat>(sign_bits: u32, exponent_bits: u32, mantissa_bits: u64, category: &FpCategory) -> String {
+    match category {
+        FpCategory::Infinite => if sign_bits == 0 { "inf".to_string() } else { "-inf".to_string() },
+        FpCategory::Nan => "NaN".to_string(),
+        FpCategory::Zero => if sign_bits == 0 { "0".to_string() } else { "-0".to_string() },
+        FpCategory::Subnormal | FpCategory::Normal => {
+            let (significand, exponent) = significand_and_exponent::<T>(exponent_bits, mantissa_bits);
+            exact_decimal::exact_decimal_string(sign_bits != 0, significand, exponent)
+        }
+    }
+}
+
+// ASN.1 (X.690) encodes a REAL's binary mantissa in canonical form: trailing
+// zero bits are shifted out of the mantissa and folded into the exponent.
+fn normalize_significand(mut significand: u64, mut exponent: i32) -> (u64, i32) {
+    while significand != 0 && significand & 1 == 0 {
+        significand >>= 1;
+        exponent += 1;
+    }
+    (significand, exponent)
+}
+
+// Minimal big-endian two's-complement octets for a signed exponent.
+fn exponent_octets(exponent: i32) -> Vec<u8> {
+    let bytes = exponent.to_be_bytes();
+    let mut start = 0;
+    while start < 3 {
+        let next_msb_set = bytes[start + 1] & 0x80 != 0;
+        let redundant = (bytes[start] == 0x00 && !next_msb_set) || (bytes[start] == 0xff && next_msb_set);
+        if !redundant {
+            break;
+        }
+        start += 1;
+    }
+    bytes[start..].to_vec()
+}
+
+// Minimal big-endian unsigned octets for a nonzero significand.
+fn mantissa_octets(significand: u64) -> Vec<u8> {
+    let bytes = significand.to_be_bytes();
+    let first_nonzero = bytes.iter().position(|&b| b != 0).unwrap_or(bytes.len() - 1);
+    bytes[first_nonzero..].to_vec()
+}
+
+// Encodes the parsed (sign, exponent, mantissa) fields as an ASN.1 REAL
+// (universal tag 0x09), binary encoding per X.690 8.5.7: an info octet with
+// bit 8 set, the sign in bit 7, base 2 in bits 6-5, no scaling factor in bits
+// 4-3, and the exponent-length format in bits 2-1, followed by the exponent
+// octets and then the unsigned mantissa octets.
+fn encode_asn1_real<T: IeeeFloat>(sign_bits: u32, exponent_bits: u32, mantissa_bits: u64) -> Vec<u8> {
+    match classify::<T>(exponent_bits, mantissa_bits) {
+        FpCategory::Zero => return vec![0x09, 0x00],
+        FpCategory::Infinite => {
+            let value = if sign_bits == 0 { 0x40 } else { 0x41 };
+            return vec![0x09, 0x01, value];
+        }
+        FpCategory::Nan => {
+            // The ASN.1 REAL type has no representation for NaN.
+            return vec![0x09, 0x00];
+        }
+        FpCategory::Subnormal | FpCategory::Normal => {}
+    }
+
+    let (significand, exponent) = significand_and_exponent::<T>(exponent_bits, mantissa_bits);
+    let (significand, exponent) = normalize_significand(significand, exponent);
+
+    let exponent_octets = exponent_octets(exponent);
+    let mantissa_octets = mantissa_octets(significand);
+
+    let mut info_byte = 0x80;
+    if sign_bits != 0 {
+        info_byte |= 0x40;
+    }
+    info_byte |= match exponent_octets.len() {
+        1 => 0b00,
+        2 => 0b01,
+        3 => 0b10,
+        _ => 0b11,
+    };
+
+    let mut content = vec![info_byte];
+    content.extend(&exponent_octets);
+    content.extend(&mantissa_octets);
+
+    let mut encoded = vec![0x09, content.len() as u8];
+    encoded.extend(content);
+    encoded
+}
 
 #[cfg(test)]
 mod tests {
+    use super::*;
+
     #[test]
     fn test_parse() {
+        assert_eq!(parse(1.0_f32), (0, 127, 0));
+        assert_eq!(parse(-1.0_f32), (1, 127, 0));
+        assert_eq!(parse(0.0_f32), (0, 0, 0));
+        assert_eq!(parse(-0.0_f32), (1, 0, 0));
 
+        assert_eq!(parse(1.0_f64), (0, 1023, 0));
+        assert_eq!(parse(-1.0_f64), (1, 1023, 0));
     }
 
     #[test]
     fn test_decode() {
+        // Normal: 1.0 == 1 * 2**0
+        assert_eq!(decode::<f32>(0, 127, 0), (1.0, 1.0, 1.0));
+        assert_eq!(decode::<f64>(0, 1023, 0), (1.0, 1.0, 1.0));
+
+        // Zero: exponent field zero, mantissa field zero, implicit bit 0.
+        let (sign, exponent, mantissa) = decode::<f32>(0, 0, 0);
+        assert_eq!(mantissa, 0.0);
+        assert_eq!(sign * exponent * mantissa, 0.0);
+
+        // Smallest subnormal: mantissa field 1, implicit bit 0, exponent 2**-126.
+        let (sign, exponent, mantissa) = decode::<f32>(0, 0, 1);
+        assert_eq!(exponent, 2_f32.powi(-126));
+        assert!((mantissa - 2_f32.powi(-23)).abs() < f32::EPSILON);
+        assert!(sign * exponent * mantissa > 0.0);
+
+        // Infinity: exponent field all ones, mantissa field zero.
+        let (sign, exponent, mantissa) = decode::<f32>(0, 0xff, 0);
+        assert!(exponent.is_infinite());
+        assert_eq!(mantissa, 0.0);
+        assert!(sign > 0.0);
 
+        // NaN: exponent field all ones, mantissa field nonzero.
+        let (_, _, mantissa) = decode::<f32>(0, 0xff, 1);
+        assert!(mantissa.is_nan());
     }
 
     #[test]
     fn test_recalculate() {
+        assert_eq!(recalculate(1.0_f32, 1.0, 1.0), 1.0);
+        assert_eq!(recalculate(-1.0_f32, 1.0, 1.0), -1.0);
+        assert!(recalculate(1.0_f32, f32::INFINITY, 0.0).is_infinite());
+        assert!(recalculate(1.0_f32, f32::INFINITY, f32::NAN).is_nan());
+    }
+
+    #[test]
+    fn test_classify() {
+        assert_eq!(classify::<f32>(127, 0), FpCategory::Normal);
+        assert_eq!(classify::<f32>(0, 0), FpCategory::Zero);
+        assert_eq!(classify::<f32>(0, 1), FpCategory::Subnormal);
+        assert_eq!(classify::<f32>(0xff, 0), FpCategory::Infinite);
+        assert_eq!(classify::<f32>(0xff, 1), FpCategory::Nan);
+
+        assert_eq!(classify::<f64>(0x7ff, 0), FpCategory::Infinite);
+    }
 
+    #[test]
+    fn test_encode_asn1_real() {
+        assert_eq!(encode_asn1_real::<f32>(0, 0, 0), vec![0x09, 0x00]);
+        assert_eq!(encode_asn1_real::<f32>(0, 0xff, 0), vec![0x09, 0x01, 0x40]);
+        assert_eq!(encode_asn1_real::<f32>(1, 0xff, 0), vec![0x09, 0x01, 0x41]);
+
+        // 1398101.25 == 0x555555 * 2**-2
+        let (sign_bits, exponent_bits, mantissa_bits) = parse(1398101.25_f32);
+        assert_eq!(
+            encode_asn1_real::<f32>(sign_bits, exponent_bits, mantissa_bits),
+            vec![0x09, 0x05, 0x80, 0xfe, 0x55, 0x55, 0x55]
+        );
+
+        // Same value, computed through f64's wider fields.
+        let (sign_bits, exponent_bits, mantissa_bits) = parse(1398101.25_f64);
+        assert_eq!(
+            encode_asn1_real::<f64>(sign_bits, exponent_bits, mantissa_bits),
+            vec![0x09, 0x05, 0x80, 0xfe, 0x55, 0x55, 0x55]
+        );
+    }
+
+    #[test]
+    fn test_from_decimal_literal() {
+        let (value, fast_path_used) = from_decimal_literal::<f32>("-1.25e-3");
+        assert_eq!(value, -1.25e-3_f32);
+        assert!(fast_path_used);
+
+        // 16777217 == 2**24 + 1 doesn't fit the exact-significand table.
+        let (value, fast_path_used) = from_decimal_literal::<f32>("16777217");
+        assert_eq!(value, "16777217".parse::<f32>().unwrap());
+        assert!(!fast_path_used);
+
+        // 1e20 is within f64's wider exact-power-of-ten table but not f32's.
+        let (value, fast_path_used) = from_decimal_literal::<f32>("1e20");
+        assert!(!fast_path_used);
+        assert_eq!(value, "1e20".parse::<f32>().unwrap());
+
+        let (value, fast_path_used) = from_decimal_literal::<f64>("1e20");
+        assert_eq!(value, 1e20_f64);
+        assert!(fast_path_used);
     }
-}
\ No newline at end of file
+}